@@ -0,0 +1,62 @@
+//! A C-callable surface for embedding the parser in non-Rust hosts, mirroring
+//! the `ffi` module pattern used by `jsonpath_lib`. Gated behind the `ffi`
+//! feature so the wasm build (which uses [`crate::parse`] instead) is
+//! unaffected.
+
+use crate::{parse_binding_spanned, ParserError, ParserResult, ParserSuccess};
+use std::ffi::{CStr, CString};
+use std::os::raw::c_char;
+
+fn to_str<'a>(raw: *const c_char) -> Option<&'a str> {
+    if raw.is_null() {
+        return None;
+    }
+
+    unsafe { CStr::from_ptr(raw) }.to_str().ok()
+}
+
+fn to_char_ptr(value: String) -> *const c_char {
+    match CString::new(value) {
+        Ok(c_string) => c_string.into_raw(),
+        Err(_) => std::ptr::null(),
+    }
+}
+
+/// Parses `raw` (a UTF-8 C string) and returns a heap-allocated C string
+/// containing the JSON serialization of a [`ParserResult`] — the same
+/// `{success, path}` / `{success, error}` shape the wasm `parse` function
+/// returns.
+///
+/// Returns a null pointer if `raw` is null or not valid UTF-8. The caller
+/// owns the returned pointer and must release it with exactly one call to
+/// [`binding_free`].
+#[no_mangle]
+pub extern "C" fn binding_parse(raw: *const c_char) -> *const c_char {
+    let raw_binding = match to_str(raw) {
+        Some(s) => s,
+        None => return std::ptr::null(),
+    };
+
+    let result = match parse_binding_spanned(raw_binding) {
+        Ok(nodes) => ParserResult::Success(ParserSuccess::new(nodes)),
+        Err(e) => ParserResult::Error(ParserError::new(e.message, e.span)),
+    };
+
+    let json = serde_json::to_string(&result).unwrap_or_else(|_| "null".to_string());
+
+    to_char_ptr(json)
+}
+
+/// Releases a C string previously returned by [`binding_parse`].
+///
+/// # Safety
+/// `ptr` must have been returned by [`binding_parse`] and must not already
+/// have been freed.
+#[no_mangle]
+pub unsafe extern "C" fn binding_free(ptr: *mut c_char) {
+    if ptr.is_null() {
+        return;
+    }
+
+    drop(CString::from_raw(ptr));
+}
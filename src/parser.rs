@@ -0,0 +1,362 @@
+//! A `nom` combinator parser for binding strings, replacing the old
+//! hand-rolled `ParsingState` (manual `current_index`/`current_char`
+//! bookkeeping). Each grammar rule below is a small function of the form
+//! `fn rule<'a>(full: &'a str, input: &'a str) -> PResult<'a, T>`: `input` is
+//! the slice still to be parsed, `full` is the original binding string,
+//! carried through so spans can be recorded as absolute offsets into it.
+//!
+//! Every `input`/`rest` value handled here is a suffix of `full` (nom only
+//! ever trims from the front), so an absolute offset is just
+//! `full.len() - suffix.len()` — no need for `nom_locate` or pointer
+//! arithmetic.
+
+use crate::{
+    is_identifier_char, AnyNode, ConcatenatedNode, ExpressionNode, ParsingError, PathNode,
+    PredicateNode, QueryNode, Span, ValueNode,
+};
+use nom::branch::alt;
+use nom::bytes::complete::{tag, take_while};
+use nom::character::complete::{char, multispace0};
+use nom::combinator::cut;
+use nom::error::{Error as NomError, ErrorKind};
+use nom::IResult;
+
+type PResult<'a, O> = IResult<&'a str, O, NomError<&'a str>>;
+
+fn offset(full: &str, sub: &str) -> usize {
+    full.len() - sub.len()
+}
+
+fn span(full: &str, start: &str, end: &str) -> Span {
+    Span::new(offset(full, start), offset(full, end))
+}
+
+/// Parses `full` as a top-level path, the way [`crate::parse_binding`] does.
+/// Unlike a nested `{{ }}` path, a top-level path that stops early on a
+/// stray `}` is accepted with the remainder simply left unconsumed — this
+/// matches the leniency the previous parser already had.
+pub(crate) fn parse(full: &str) -> Result<PathNode, ParsingError> {
+    match path_body(full, full) {
+        Ok((rest, parts)) => Ok(PathNode::new(parts, span(full, full, rest))),
+        Err(err) => Err(to_parsing_error(full, err)),
+    }
+}
+
+fn to_parsing_error<'a>(full: &'a str, err: nom::Err<NomError<&'a str>>) -> ParsingError {
+    let remaining = match err {
+        nom::Err::Error(e) | nom::Err::Failure(e) => e.input,
+        nom::Err::Incomplete(_) => "",
+    };
+
+    let start = offset(full, remaining);
+    let (message, end) = match remaining.chars().next() {
+        Some(c) => (format!("Unexpected character: '{}'", c), start + c.len_utf8()),
+        None => ("Unexpected end of input".to_string(), start),
+    };
+
+    ParsingError {
+        message,
+        span: Span::new(start, end),
+    }
+}
+
+/// A `.`-separated list of `segment_and_brackets`, stopping at end-of-input
+/// or a `}` (so the caller — either [`parse`] or [`nested_path`] — can decide
+/// what that means).
+fn path_body<'a>(full: &'a str, input: &'a str) -> PResult<'a, Vec<AnyNode>> {
+    let mut parts = Vec::new();
+    let mut rest = input;
+
+    loop {
+        let (next_rest, segment) = segment_and_brackets(full, rest)?;
+        let segment_was_empty = segment.is_empty();
+        parts.extend(segment);
+        rest = next_rest;
+
+        if rest.is_empty() || rest.starts_with('}') {
+            break;
+        }
+
+        if segment_was_empty {
+            return Err(nom::Err::Error(NomError::new(rest, ErrorKind::Fail)));
+        }
+
+        match rest.strip_prefix('.') {
+            Some(after_dot) => rest = after_dot,
+            None => return Err(nom::Err::Error(NomError::new(rest, ErrorKind::Char))),
+        }
+    }
+
+    Ok((rest, parts))
+}
+
+/// A single segment (possibly a concatenation of several adjacent
+/// `simple_segment`s, e.g. `foo{bar}`) followed by zero or more `[...]`
+/// bracket filters.
+fn segment_and_brackets<'a>(full: &'a str, input: &'a str) -> PResult<'a, Vec<AnyNode>> {
+    let (rest, mut parts) = segment(full, input)?;
+    let (rest, brackets) = nom::multi::many0(|i| bracket(full, i))(rest)?;
+    parts.extend(brackets);
+    Ok((rest, parts))
+}
+
+/// Zero, one, or several adjacent [`simple_segment`]s. Several are folded
+/// into a single `ConcatenatedNode`, matching how `foo{bar}baz` renders as
+/// one path element rather than three.
+fn segment<'a>(full: &'a str, input: &'a str) -> PResult<'a, Vec<AnyNode>> {
+    let start = input;
+    let (rest, mut nodes) = nom::multi::many0(|i| simple_segment(full, i))(input)?;
+
+    if nodes.is_empty() {
+        return Ok((rest, Vec::new()));
+    }
+
+    if nodes.len() == 1 {
+        return Ok((rest, vec![nodes.remove(0)]));
+    }
+
+    let concatenated = ConcatenatedNode::from((nodes, span(full, start, rest)));
+    Ok((rest, vec![AnyNode::from(concatenated)]))
+}
+
+/// A nested path (`{{ }}`), an expression (`{ }`), or a bare identifier.
+fn simple_segment<'a>(full: &'a str, input: &'a str) -> PResult<'a, AnyNode> {
+    alt((
+        |i| nested_path(full, i).map(|(r, n)| (r, AnyNode::from(n))),
+        |i| expression(full, i).map(|(r, n)| (r, AnyNode::from(n))),
+        |i| identifier(full, i).map(|(r, n)| (r, AnyNode::from(n))),
+    ))(input)
+}
+
+/// `{{ path }}` — a path whose resolved value becomes the key of the
+/// enclosing segment.
+fn nested_path<'a>(full: &'a str, input: &'a str) -> PResult<'a, PathNode> {
+    let (rest, _) = tag("{{")(input)?;
+    let (rest, parts) = cut(|i| path_body(full, i))(rest)?;
+    let (rest, _) = cut(tag("}}"))(rest)?;
+    Ok((rest, PathNode::new(parts, span(full, input, rest))))
+}
+
+/// `{ identifier }` — an identifier looked up in the resolve-time model.
+fn expression<'a>(full: &'a str, input: &'a str) -> PResult<'a, ExpressionNode> {
+    let (rest, _) = char('{')(input)?;
+    let (rest, _) = multispace0(rest)?;
+    let (rest, value) = cut(|i| identifier(full, i))(rest)?;
+    let (rest, _) = multispace0(rest)?;
+    let (rest, _) = cut(char('}'))(rest)?;
+
+    let mut node = ExpressionNode::from(value);
+    node.span = span(full, input, rest);
+    Ok((rest, node))
+}
+
+/// A run of identifier characters (alphanumeric, `_`, `-`, `@`).
+fn identifier<'a>(full: &'a str, input: &'a str) -> PResult<'a, ValueNode> {
+    let (rest, text) = nom::bytes::complete::take_while1(|c: char| is_identifier_char(Some(c)))(input)?;
+    Ok((rest, ValueNode::from(text.to_string()).with_span(span(full, input, rest))))
+}
+
+/// A run of ASCII digits (possibly empty).
+fn digits(input: &str) -> PResult<'_, &str> {
+    take_while(|c: char| c.is_ascii_digit())(input)
+}
+
+/// An optional sign, digits, an optional fractional part, and an optional
+/// exponent. Falls back to [`identifier`] (by failing without consuming
+/// anything) if the numeric-looking prefix is immediately followed by more
+/// identifier characters, e.g. `"3abc"`.
+fn number<'a>(full: &'a str, input: &'a str) -> PResult<'a, ValueNode> {
+    let mut rest = input;
+
+    if let Ok((r, _)) = alt((char::<_, NomError<&str>>('+'), char('-')))(rest) {
+        rest = r;
+    }
+
+    let (r, integer_part) = digits(rest)?;
+    rest = r;
+
+    let mut has_fraction = false;
+    if let Some(after_dot) = rest.strip_prefix('.') {
+        if matches!(after_dot.chars().next(), Some(c) if c.is_ascii_digit()) {
+            has_fraction = true;
+            let (r, _) = digits(after_dot)?;
+            rest = r;
+        }
+    }
+
+    if matches!(rest.chars().next(), Some('e') | Some('E')) {
+        let before_exponent = rest;
+        let mut exponent_rest = &rest[1..];
+
+        if let Ok((r, _)) = alt((char::<_, NomError<&str>>('+'), char('-')))(exponent_rest) {
+            exponent_rest = r;
+        }
+
+        let (r, exponent_digits) = digits(exponent_rest)?;
+        rest = if exponent_digits.is_empty() {
+            before_exponent
+        } else {
+            r
+        };
+    }
+
+    let trailing_is_identifier = matches!(rest.chars().next(), Some(c) if is_identifier_char(Some(c)));
+    if (integer_part.is_empty() && !has_fraction) || trailing_is_identifier {
+        return Err(nom::Err::Error(NomError::new(input, ErrorKind::Digit)));
+    }
+
+    let text = &input[..input.len() - rest.len()];
+    match text.parse::<f32>() {
+        Ok(number) => Ok((rest, ValueNode::from(number).with_span(span(full, input, rest)))),
+        Err(_) => Err(nom::Err::Error(NomError::new(input, ErrorKind::Float))),
+    }
+}
+
+/// A numeric literal if the whole token is numeric, an identifier otherwise.
+fn literal<'a>(full: &'a str, input: &'a str) -> PResult<'a, AnyNode> {
+    alt((
+        |i| number(full, i).map(|(r, n)| (r, AnyNode::from(n))),
+        |i| identifier(full, i).map(|(r, n)| (r, AnyNode::from(n))),
+    ))(input)
+}
+
+/// A single-or-double-quoted string.
+fn quoted_segment<'a>(full: &'a str, input: &'a str) -> PResult<'a, AnyNode> {
+    let (rest, quote) = alt((char('\''), char('"')))(input)?;
+    let (rest, contents) = take_while(|c: char| c != '\'' && c != '"')(rest)?;
+    let (rest, _) = cut(char(quote))(rest)?;
+
+    let value = ValueNode::from(contents.to_string()).with_span(span(full, input, rest));
+    Ok((rest, AnyNode::from(value)))
+}
+
+/// A [`quoted_segment`], or (if unquoted) a [`literal`].
+fn optionally_quoted_segment<'a>(full: &'a str, input: &'a str) -> PResult<'a, AnyNode> {
+    let (rest, _) = multispace0(input)?;
+    alt((|i| quoted_segment(full, i), |i| literal(full, i)))(rest)
+}
+
+/// A run of `=` characters, consumed as a single assignment marker.
+fn equals(input: &str) -> PResult<'_, bool> {
+    let (rest, eqs) = take_while(|c| c == '=')(input)?;
+    Ok((rest, !eqs.is_empty()))
+}
+
+/// A single `key` or `key=value` query, or a parenthesized sub-predicate.
+fn predicate_primary<'a>(full: &'a str, input: &'a str) -> PResult<'a, PredicateNode> {
+    let (rest, _) = multispace0(input)?;
+
+    if let Ok((rest, _)) = char::<_, NomError<&str>>('(')(rest) {
+        let (rest, _) = multispace0(rest)?;
+        let (rest, inner) = cut(|i| predicate_or(full, i))(rest)?;
+        let (rest, _) = multispace0(rest)?;
+        let (rest, _) = cut(char(')'))(rest)?;
+        return Ok((rest, inner));
+    }
+
+    let start = rest;
+    let (rest, key) = optionally_quoted_segment(full, rest)?;
+    let (rest, _) = multispace0(rest)?;
+    let (rest, has_equals) = equals(rest)?;
+
+    let (rest, value) = if has_equals {
+        let (rest, _) = multispace0(rest)?;
+        let (rest, value) = cut(|i| optionally_quoted_segment(full, i))(rest)?;
+        let (rest, _) = multispace0(rest)?;
+        (rest, Some(value))
+    } else {
+        (rest, None)
+    };
+
+    let node = PredicateNode::Query(QueryNode::new(key, value, span(full, start, rest)));
+    Ok((rest, node))
+}
+
+/// `!` binds tightest; `!!a=1` negates twice, same as no negation.
+fn predicate_not<'a>(full: &'a str, input: &'a str) -> PResult<'a, PredicateNode> {
+    let (rest, _) = multispace0(input)?;
+
+    if let Ok((rest, _)) = char::<_, NomError<&str>>('!')(rest) {
+        let (rest, inner) = cut(|i| predicate_not(full, i))(rest)?;
+        return Ok((rest, PredicateNode::Not(Box::new(inner))));
+    }
+
+    predicate_primary(full, rest)
+}
+
+/// `&&` binds tighter than `||`.
+fn predicate_and<'a>(full: &'a str, input: &'a str) -> PResult<'a, PredicateNode> {
+    let (rest, first) = predicate_not(full, input)?;
+    let mut parts = vec![first];
+    let mut rest = rest;
+
+    loop {
+        let (after_ws, _) = multispace0(rest)?;
+
+        match tag::<_, _, NomError<&str>>("&&")(after_ws) {
+            Ok((after_and, _)) => {
+                let (after_ws, _) = multispace0(after_and)?;
+                let (next_rest, next) = cut(|i| predicate_not(full, i))(after_ws)?;
+                parts.push(next);
+                rest = next_rest;
+            }
+            Err(_) => {
+                rest = after_ws;
+                break;
+            }
+        }
+    }
+
+    if parts.len() == 1 {
+        Ok((rest, parts.remove(0)))
+    } else {
+        Ok((rest, PredicateNode::And(parts)))
+    }
+}
+
+fn predicate_or<'a>(full: &'a str, input: &'a str) -> PResult<'a, PredicateNode> {
+    let (rest, first) = predicate_and(full, input)?;
+    let mut parts = vec![first];
+    let mut rest = rest;
+
+    loop {
+        let (after_ws, _) = multispace0(rest)?;
+
+        match tag::<_, _, NomError<&str>>("||")(after_ws) {
+            Ok((after_or, _)) => {
+                let (after_ws, _) = multispace0(after_or)?;
+                let (next_rest, next) = cut(|i| predicate_and(full, i))(after_ws)?;
+                parts.push(next);
+                rest = next_rest;
+            }
+            Err(_) => {
+                rest = after_ws;
+                break;
+            }
+        }
+    }
+
+    if parts.len() == 1 {
+        Ok((rest, parts.remove(0)))
+    } else {
+        Ok((rest, PredicateNode::Or(parts)))
+    }
+}
+
+/// `[ predicate ]` — rendered as a bare `QueryNode` path element when the
+/// predicate is a single leaf, or a `PredicateNode` when it's a compound
+/// `&&`/`||`/`!` filter.
+fn bracket<'a>(full: &'a str, input: &'a str) -> PResult<'a, AnyNode> {
+    let (rest, _) = char('[')(input)?;
+    let (rest, _) = multispace0(rest)?;
+    let (rest, predicate) = cut(|i| predicate_or(full, i))(rest)?;
+    let (rest, _) = multispace0(rest)?;
+    let (rest, _) = cut(char(']'))(rest)?;
+
+    let node = match predicate {
+        PredicateNode::Query(query) => AnyNode::from(query),
+        other => AnyNode::from(other),
+    };
+
+    Ok((rest, node))
+}
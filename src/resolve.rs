@@ -0,0 +1,391 @@
+//! Evaluates a parsed [`Path`] against a JSON-like data model, the way
+//! jsonpath selection walks a document.
+//!
+//! A [`ValueNode`](crate::ValueNode) segment does an object-key (or
+//! array-index) lookup on the current value. A
+//! [`QueryNode`](crate::QueryNode) filters an array, keeping elements whose
+//! `key` field equals `value` (or, when `value` is absent, elements where
+//! `key` is present and truthy). A [`PredicateNode`](crate::PredicateNode)
+//! filters the same way but combines several queries with `&&`/`||`/`!`. A
+//! `ConcatenatedNode` resolves each of its parts to a string and joins them
+//! to form the effective key before indexing. An `ExpressionNode` is
+//! resolved by looking its identifier up in the supplied `model` map. A
+//! nested `PathNode` (e.g. `foo.{{bar}}`) is resolved against the *root*
+//! document (the same `data` passed to [`resolve`]), not the value of the
+//! segment it's nested under, and that value (coerced to a string) is used
+//! as the key for the enclosing segment.
+
+use crate::{
+    AnyNode, ConcatableNode, ConcatenatedNode, Path, PredicateNode, QueryNode, ValueNodeValue,
+};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fmt;
+
+/// An error produced while resolving a [`Path`] against data.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ResolveError {
+    /// No value could be found for the path segment at `segment_index`
+    /// (missing object key, out-of-range array index, unknown expression,
+    /// or a filter applied to a non-array). A filter that matches nothing
+    /// yields an empty array, not this error.
+    NotFound { segment_index: usize },
+}
+
+impl fmt::Display for ResolveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ResolveError::NotFound { segment_index } => {
+                write!(f, "no value found for path segment {}", segment_index)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ResolveError {}
+
+/// Resolves `path` against `data`, looking up any `{expression}` segments in
+/// `model`.
+pub fn resolve(
+    path: &Path,
+    data: &Value,
+    model: &HashMap<String, Value>,
+) -> Result<Value, ResolveError> {
+    let mut current = data.clone();
+
+    for (segment_index, node) in path.iter().enumerate() {
+        current = resolve_segment(node, data, &current, model, segment_index)?;
+    }
+
+    Ok(current)
+}
+
+fn resolve_segment(
+    node: &AnyNode,
+    root: &Value,
+    current: &Value,
+    model: &HashMap<String, Value>,
+    segment_index: usize,
+) -> Result<Value, ResolveError> {
+    match node {
+        AnyNode::Value(value_node) => {
+            let key = value_node_to_key(&value_node.value);
+            index(current, &key, segment_index)
+        }
+        AnyNode::Expression(expression_node) => model
+            .get(&expression_node.value)
+            .cloned()
+            .ok_or(ResolveError::NotFound { segment_index }),
+        AnyNode::Concatenated(concatenated_node) => {
+            let key = resolve_concatenated(concatenated_node, root, model, segment_index)?;
+            index(current, &key, segment_index)
+        }
+        AnyNode::Path(path_node) => {
+            let resolved = resolve(&path_node.path, root, model)
+                .map_err(|_| ResolveError::NotFound { segment_index })?;
+            let key = value_to_string(&resolved);
+            index(current, &key, segment_index)
+        }
+        AnyNode::Query(query_node) => filter(current, query_node, root, model, segment_index),
+        AnyNode::Predicate(predicate_node) => {
+            filter_predicate(current, predicate_node, root, model, segment_index)
+        }
+    }
+}
+
+fn resolve_concatenated(
+    node: &ConcatenatedNode,
+    root: &Value,
+    model: &HashMap<String, Value>,
+    segment_index: usize,
+) -> Result<String, ResolveError> {
+    let mut key = String::new();
+
+    for part in &node.value {
+        let piece = match part {
+            ConcatableNode::Value(value_node) => value_node_to_key(&value_node.value),
+            ConcatableNode::Expression(expression_node) => model
+                .get(&expression_node.value)
+                .map(value_to_string)
+                .ok_or(ResolveError::NotFound { segment_index })?,
+            ConcatableNode::Path(path_node) => {
+                let resolved = resolve(&path_node.path, root, model)
+                    .map_err(|_| ResolveError::NotFound { segment_index })?;
+                value_to_string(&resolved)
+            }
+        };
+
+        key.push_str(&piece);
+    }
+
+    Ok(key)
+}
+
+fn resolve_key(
+    node: &AnyNode,
+    root: &Value,
+    model: &HashMap<String, Value>,
+    segment_index: usize,
+) -> Result<String, ResolveError> {
+    match node {
+        AnyNode::Value(value_node) => Ok(value_node_to_key(&value_node.value)),
+        AnyNode::Expression(expression_node) => model
+            .get(&expression_node.value)
+            .map(value_to_string)
+            .ok_or(ResolveError::NotFound { segment_index }),
+        AnyNode::Concatenated(concatenated_node) => {
+            resolve_concatenated(concatenated_node, root, model, segment_index)
+        }
+        AnyNode::Path(path_node) => {
+            let resolved = resolve(&path_node.path, root, model)
+                .map_err(|_| ResolveError::NotFound { segment_index })?;
+            Ok(value_to_string(&resolved))
+        }
+        AnyNode::Query(_) | AnyNode::Predicate(_) => Err(ResolveError::NotFound { segment_index }),
+    }
+}
+
+fn filter(
+    current: &Value,
+    query: &QueryNode,
+    root: &Value,
+    model: &HashMap<String, Value>,
+    segment_index: usize,
+) -> Result<Value, ResolveError> {
+    let items = current
+        .as_array()
+        .ok_or(ResolveError::NotFound { segment_index })?;
+
+    let matches = items
+        .iter()
+        .filter(|item| query_matches(item, query, root, model, segment_index).unwrap_or(false))
+        .cloned()
+        .collect();
+
+    Ok(Value::Array(matches))
+}
+
+/// Filters an array, keeping elements that satisfy the full `&&`/`||`/`!`
+/// predicate tree from a compound bracket filter like `[a=1 && b=2]`.
+fn filter_predicate(
+    current: &Value,
+    predicate: &PredicateNode,
+    root: &Value,
+    model: &HashMap<String, Value>,
+    segment_index: usize,
+) -> Result<Value, ResolveError> {
+    let items = current
+        .as_array()
+        .ok_or(ResolveError::NotFound { segment_index })?;
+
+    let matches = items
+        .iter()
+        .filter(|item| {
+            matches_predicate(item, predicate, root, model, segment_index).unwrap_or(false)
+        })
+        .cloned()
+        .collect();
+
+    Ok(Value::Array(matches))
+}
+
+fn matches_predicate(
+    item: &Value,
+    predicate: &PredicateNode,
+    root: &Value,
+    model: &HashMap<String, Value>,
+    segment_index: usize,
+) -> Result<bool, ResolveError> {
+    match predicate {
+        PredicateNode::Query(query) => query_matches(item, query, root, model, segment_index),
+        PredicateNode::Not(inner) => {
+            Ok(!matches_predicate(item, inner, root, model, segment_index)?)
+        }
+        PredicateNode::And(parts) => {
+            for part in parts {
+                if !matches_predicate(item, part, root, model, segment_index)? {
+                    return Ok(false);
+                }
+            }
+            Ok(true)
+        }
+        PredicateNode::Or(parts) => {
+            for part in parts {
+                if matches_predicate(item, part, root, model, segment_index)? {
+                    return Ok(true);
+                }
+            }
+            Ok(false)
+        }
+    }
+}
+
+fn query_matches(
+    item: &Value,
+    query: &QueryNode,
+    root: &Value,
+    model: &HashMap<String, Value>,
+    segment_index: usize,
+) -> Result<bool, ResolveError> {
+    let key = resolve_key(&query.key, root, model, segment_index)?;
+
+    match &query.value {
+        Some(value_node) => {
+            let expected = resolve_key(value_node, root, model, segment_index)?;
+            Ok(item
+                .get(&key)
+                .map(|v| value_to_string(v) == expected)
+                .unwrap_or(false))
+        }
+        None => Ok(item.get(&key).map(is_truthy).unwrap_or(false)),
+    }
+}
+
+fn index(current: &Value, key: &str, segment_index: usize) -> Result<Value, ResolveError> {
+    match current {
+        Value::Object(map) => map
+            .get(key)
+            .cloned()
+            .ok_or(ResolveError::NotFound { segment_index }),
+        Value::Array(items) => key
+            .parse::<usize>()
+            .ok()
+            .and_then(|i| items.get(i))
+            .cloned()
+            .ok_or(ResolveError::NotFound { segment_index }),
+        _ => Err(ResolveError::NotFound { segment_index }),
+    }
+}
+
+fn value_node_to_key(value: &ValueNodeValue) -> String {
+    match value {
+        ValueNodeValue::String(s) => s.clone(),
+        ValueNodeValue::Number(n) => n.to_string(),
+    }
+}
+
+fn value_to_string(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+fn is_truthy(value: &Value) -> bool {
+    match value {
+        Value::Null => false,
+        Value::Bool(b) => *b,
+        Value::Number(n) => n.as_f64().map(|f| f != 0.0).unwrap_or(true),
+        Value::String(s) => !s.is_empty(),
+        Value::Array(a) => !a.is_empty(),
+        Value::Object(o) => !o.is_empty(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_binding;
+    use serde_json::json;
+
+    fn resolve_str(binding: &str, data: &Value, model: &HashMap<String, Value>) -> Result<Value, ResolveError> {
+        let path = parse_binding(binding).expect("parse_binding failed");
+        resolve(&path, data, model)
+    }
+
+    #[test]
+    fn resolves_object_key() {
+        let data = json!({"foo": {"bar": 1}});
+        let model = HashMap::new();
+        let result = resolve_str("foo.bar", &data, &model).expect("resolve failed");
+        assert_eq!(result, json!(1));
+    }
+
+    #[test]
+    fn resolves_array_index() {
+        let data = json!({"items": ["a", "b", "c"]});
+        let model = HashMap::new();
+        let result = resolve_str("items.1", &data, &model).expect("resolve failed");
+        assert_eq!(result, json!("b"));
+    }
+
+    #[test]
+    fn missing_object_key_is_not_found_at_segment_index() {
+        let data = json!({"foo": {}});
+        let model = HashMap::new();
+        let err = resolve_str("foo.bar", &data, &model).unwrap_err();
+        assert_eq!(err, ResolveError::NotFound { segment_index: 1 });
+    }
+
+    #[test]
+    fn out_of_range_array_index_is_not_found() {
+        let data = json!({"items": ["a"]});
+        let model = HashMap::new();
+        let err = resolve_str("items.5", &data, &model).unwrap_err();
+        assert_eq!(err, ResolveError::NotFound { segment_index: 1 });
+    }
+
+    #[test]
+    fn query_filters_by_string_value() {
+        let data = json!({
+            "items": [{"id": "a", "n": 1}, {"id": "b", "n": 2}]
+        });
+        let model = HashMap::new();
+        let result = resolve_str("items[id=b]", &data, &model).expect("resolve failed");
+        assert_eq!(result, json!([{"id": "b", "n": 2}]));
+    }
+
+    #[test]
+    fn query_filters_by_numeric_value() {
+        let data = json!({
+            "items": [{"id": "a", "n": 1}, {"id": "b", "n": 2}]
+        });
+        let model = HashMap::new();
+        let result = resolve_str("items[n=2]", &data, &model).expect("resolve failed");
+        assert_eq!(result, json!([{"id": "b", "n": 2}]));
+    }
+
+    #[test]
+    fn query_without_value_keeps_truthy_entries() {
+        let data = json!({
+            "items": [{"flag": true}, {"flag": false}, {"other": 1}]
+        });
+        let model = HashMap::new();
+        let result = resolve_str("items[flag]", &data, &model).expect("resolve failed");
+        assert_eq!(result, json!([{"flag": true}]));
+    }
+
+    #[test]
+    fn predicate_combines_queries_with_and_or_not() {
+        let data = json!({
+            "items": [
+                {"a": 1, "b": 2},
+                {"a": 1, "b": 3},
+                {"a": 9, "b": 2}
+            ]
+        });
+        let model = HashMap::new();
+
+        let and_result = resolve_str("items[a=1 && b=2]", &data, &model).expect("resolve failed");
+        assert_eq!(and_result, json!([{"a": 1, "b": 2}]));
+
+        let or_result = resolve_str("items[a=9 || b=3]", &data, &model).expect("resolve failed");
+        assert_eq!(or_result, json!([{"a": 1, "b": 3}, {"a": 9, "b": 2}]));
+
+        let not_result = resolve_str("items[!a=1]", &data, &model).expect("resolve failed");
+        assert_eq!(not_result, json!([{"a": 9, "b": 2}]));
+    }
+
+    #[test]
+    fn nested_path_resolves_against_root_not_enclosing_segment() {
+        // The key named by `{{which}}` lives at the root, not inside `foo` —
+        // `foo.{{which}}` must still find it.
+        let data = json!({
+            "which": "bar",
+            "foo": {"bar": 42}
+        });
+        let model = HashMap::new();
+        let result = resolve_str("foo.{{which}}", &data, &model).expect("resolve failed");
+        assert_eq!(result, json!(42));
+    }
+}
@@ -0,0 +1,188 @@
+//! Renders a parsed [`Path`] back into a canonical binding string — the
+//! inverse of [`parse_binding`](crate::parse_binding).
+//!
+//! Segments are joined by `.`, expressions are wrapped in `{ }`, nested
+//! paths in `{{ }}`, and bracket/query segments render as `[key=value]`,
+//! quoting the key/value when it contains non-identifier characters (or,
+//! inside brackets, when a string key/value would re-parse as a number).
+
+use crate::{
+    is_identifier_char, AnyNode, ConcatableNode, ConcatenatedNode, ExpressionNode, Path, PathNode,
+    PredicateNode, QueryNode, ValueNode, ValueNodeValue,
+};
+use std::fmt;
+
+/// Types that can be rendered back into a canonical binding string.
+///
+/// Node types (`AnyNode`, `ValueNode`, ...) also implement [`fmt::Display`]
+/// with the same output; this trait exists so `Path` (a `Vec<AnyNode>`) has
+/// somewhere to hang the same method, since `Display` can't be implemented
+/// directly on a `Vec`.
+pub trait ToBindingString {
+    fn to_binding_string(&self) -> String;
+}
+
+impl ToBindingString for Path {
+    fn to_binding_string(&self) -> String {
+        let mut rendered = String::new();
+
+        for (i, node) in self.iter().enumerate() {
+            if i > 0 && !is_bracket(node) {
+                rendered.push('.');
+            }
+            rendered.push_str(&node.to_string());
+        }
+
+        rendered
+    }
+}
+
+/// Whether `node` renders as a `[...]` bracket filter, which attaches
+/// directly to the segment before it (`foo[bar]`, not `foo.[bar]`).
+fn is_bracket(node: &AnyNode) -> bool {
+    matches!(node, AnyNode::Query(_) | AnyNode::Predicate(_))
+}
+
+impl ToBindingString for AnyNode {
+    fn to_binding_string(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl fmt::Display for AnyNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AnyNode::Path(node) => write!(f, "{}", node),
+            AnyNode::Query(node) => write!(f, "{}", node),
+            AnyNode::Predicate(node) => write!(f, "[{}]", node),
+            AnyNode::Value(node) => write!(f, "{}", node),
+            AnyNode::Expression(node) => write!(f, "{}", node),
+            AnyNode::Concatenated(node) => write!(f, "{}", node),
+        }
+    }
+}
+
+impl fmt::Display for PathNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{{{{{}}}}}", self.path.to_binding_string())
+    }
+}
+
+impl fmt::Display for ValueNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.value {
+            ValueNodeValue::String(s) => write!(f, "{}", quote_if_needed(s)),
+            ValueNodeValue::Number(n) => write!(f, "{}", n),
+        }
+    }
+}
+
+impl fmt::Display for ExpressionNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{{{}}}", self.value)
+    }
+}
+
+impl fmt::Display for ConcatenatedNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for part in &self.value {
+            write!(f, "{}", part)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for ConcatableNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConcatableNode::Path(node) => write!(f, "{}", node),
+            ConcatableNode::Value(node) => write!(f, "{}", node),
+            ConcatableNode::Expression(node) => write!(f, "{}", node),
+        }
+    }
+}
+
+impl fmt::Display for QueryNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}]", query_inner(self))
+    }
+}
+
+impl fmt::Display for PredicateNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PredicateNode::Query(query) => write!(f, "{}", query_inner(query)),
+            PredicateNode::Not(inner) => write!(f, "!{}", wrap_unless_leaf(inner)),
+            PredicateNode::And(parts) => write!(
+                f,
+                "{}",
+                parts
+                    .iter()
+                    .map(wrap_if_or)
+                    .collect::<Vec<_>>()
+                    .join(" && ")
+            ),
+            PredicateNode::Or(parts) => write!(
+                f,
+                "{}",
+                parts
+                    .iter()
+                    .map(|p| match p {
+                        PredicateNode::Or(_) => format!("({})", p),
+                        _ => p.to_string(),
+                    })
+                    .collect::<Vec<_>>()
+                    .join(" || ")
+            ),
+        }
+    }
+}
+
+/// Renders the `key` or `key=value` body of a query, without the
+/// surrounding `[ ]` (those are added once, where the query sits in an
+/// `AnyNode` or a larger `PredicateNode`).
+fn query_inner(query: &QueryNode) -> String {
+    // Inside brackets an unquoted numeric token parses as a `Number`, so a
+    // string that looks numeric keeps its quotes.
+    let operand = |node: &AnyNode| match node {
+        AnyNode::Value(value) => match &value.value {
+            ValueNodeValue::String(s) if s.parse::<f32>().is_ok() => format!("'{}'", s),
+            _ => node.to_string(),
+        },
+        _ => node.to_string(),
+    };
+
+    match &query.value {
+        Some(value) => format!("{}={}", operand(&query.key), operand(value)),
+        None => operand(&query.key),
+    }
+}
+
+/// Parenthesizes anything other than a bare query leaf, so `!(a=1 && b=2)`
+/// round-trips instead of becoming the ambiguous `!a=1 && b=2`.
+fn wrap_unless_leaf(node: &PredicateNode) -> String {
+    match node {
+        PredicateNode::Query(_) => node.to_string(),
+        _ => format!("({})", node),
+    }
+}
+
+/// Parenthesizes an `Or` nested inside an `And`, since `&&` binds tighter,
+/// and an `And` nested inside an `And`, which would otherwise re-parse
+/// flattened into its parent.
+fn wrap_if_or(node: &PredicateNode) -> String {
+    match node {
+        PredicateNode::Or(_) | PredicateNode::And(_) => format!("({})", node),
+        _ => node.to_string(),
+    }
+}
+
+/// Quotes `value` with single quotes if it contains anything other than
+/// identifier characters, so it survives a round-trip through the parser.
+fn quote_if_needed(value: &str) -> String {
+    if !value.is_empty() && value.chars().all(|c| is_identifier_char(Some(c))) {
+        value.to_string()
+    } else {
+        format!("'{}'", value)
+    }
+}
@@ -42,3 +42,36 @@ fn basic_double() {
     ];
     assert_results_equal(expected, result);
 }
+
+/// Property: for every binding string below, `parse_binding(x).to_binding_string()`
+/// re-parses to an AST equal to the one `x` itself parses to, so the
+/// serializer and parser stay in sync as either one evolves.
+#[wasm_bindgen_test]
+fn round_trip_property() {
+    let samples = vec![
+        "",
+        "foo",
+        "{expr}",
+        "foo.bar",
+        "foo.bar.baz",
+        "foo.{expr}",
+        "{{foo.bar}}",
+        "foo[bar]",
+        "foo[bar=baz]",
+    ];
+
+    for sample in samples {
+        let parsed = parse_binding(sample).expect("parse_binding failed");
+        let rendered = parsed.to_binding_string();
+        assert!(
+            !rendered.contains(".["),
+            "{:?} rendered a bracket with a leading dot: {:?}",
+            sample,
+            rendered
+        );
+
+        let reparsed = parse_binding(&rendered).expect("to_binding_string produced unparsable output");
+
+        assert_results_equal(parsed, reparsed);
+    }
+}